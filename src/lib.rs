@@ -1,18 +1,198 @@
 use peg;
 
 pub mod ast {
-    #[derive(Debug, PartialEq, Eq)]
+    #[derive(Debug, Clone, PartialEq, Eq)]
     pub enum Arg {
         Var(String),
-        Word(String),
+        Word(Vec<Segment>),
+        // A `^`-join of two operands, e.g. `$prefix^.txt`. Left unresolved here since nothing
+        // in this crate joins segment text yet; an evaluator would flatten each side to a
+        // string and concatenate them.
+        Concat(Box<Arg>, Box<Arg>),
+    }
+
+    // A word is split into runs of literal text and `$name` interpolations so that
+    // e.g. `$dir/file.$ext` keeps both variable references instead of collapsing to
+    // one opaque string.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum SegmentKind {
+        Literal,
+        Variable,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct Segment {
+        pub text: String,
+        pub kind: SegmentKind,
     }
 
     pub type List = Vec<Arg>;
 
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct Command {
+        pub name: Arg,
+        pub args: List,
+        pub redirects: Vec<Redirect>,
+    }
+
+    pub type Pipeline = Vec<Command>;
+
+    // A connector decides whether the next pipeline runs only if the previous one
+    // succeeded (`&&`) or only if it failed (`||`).
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Connector {
+        And,
+        Or,
+    }
+
+    // An initial pipeline followed by zero or more `&&`/`||`-connected pipelines. `&&` and
+    // `||` bind tighter than the `;`/`&` that terminate the whole list.
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct AndOr {
+        pub head: Pipeline,
+        pub tail: Vec<(Connector, Pipeline)>,
+    }
+
+    // Whether a command list runs to completion before the next one starts (`;`, or a
+    // newline), or is backgrounded and the next one starts immediately (`&`).
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum Terminator {
+        Sequential,
+        Async,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum RedirectDir {
+        In,
+        Out,
+        Append,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum RedirectTo {
+        Fd(i32),
+        File(Arg),
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub struct Redirect {
+        pub from: i32,
+        pub to: RedirectTo,
+        pub dir: RedirectDir,
+    }
+
+    // A multi-command pipeline on its own, with no `&&`/`||` chaining, is represented as a
+    // `List` whose `AndOr` has an empty `tail` rather than its own variant; that's the only
+    // shape `script()` ever produces for one, so there's a single code path from a pipeline's
+    // text to its AST rather than two that could disagree.
     #[derive(Debug, PartialEq, Eq)]
     pub enum Stmt {
         Assignment(Arg, List),
         Command(Arg, List),
+        List(AndOr, Terminator),
+    }
+}
+
+// A parsed command within a pipeline may carry either arguments or a redirection; this
+// distinguishes the two while scanning so they can be collected into separate fields. An
+// argument position is a list (rather than a single `Arg`) since `^` may distribute it across
+// several arguments, e.g. `pre^(x y)`.
+enum CommandPart {
+    Args(ast::List),
+    Redirect(ast::Redirect),
+}
+
+// Implements rc's "free careting": `^` joins two lists positionally, broadcasting a
+// single-element side across the other side's length. Lists of differing, non-unit lengths
+// cannot be joined.
+fn concat(a: ast::List, b: ast::List) -> Result<ast::List, &'static str> {
+    let n = match (a.len(), b.len()) {
+        (x, y) if x == y => x,
+        (1, y) => y,
+        (x, 1) => x,
+        _ => return Err("cannot concatenate lists of differing length"),
+    };
+    let broadcast = |list: ast::List| -> ast::List {
+        if list.len() == n {
+            list
+        } else {
+            list.into_iter().cycle().take(n).collect()
+        }
+    };
+    Ok(broadcast(a)
+        .into_iter()
+        .zip(broadcast(b))
+        .map(|(x, y)| ast::Arg::Concat(Box::new(x), Box::new(y)))
+        .collect())
+}
+
+// A redirect target is a single `Arg`, but `element()` scans `^` concatenation and so may yield
+// a list of more than one value (e.g. `(a b)^(1 2)`, or a parenthesised list such as `(a b)`). That
+// has nowhere to go in a `RedirectTo::File`, so it's rejected rather than silently truncated.
+fn redirect_target(list: ast::List) -> Result<ast::Arg, &'static str> {
+    let mut it = list.into_iter();
+    match (it.next(), it.next()) {
+        (Some(a), None) => Ok(a),
+        _ => Err("redirect target must be a single value"),
+    }
+}
+
+// Resolves the backslash escapes recognised inside a double-quoted word: `\n`, `\t`, `\\`,
+// `\"` and `\$`. Any other escape (e.g. `\x`) drops the backslash and keeps the following
+// character literally.
+fn strip_basic_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+// Only a genuinely compound and/or list (a connector chain, a pipeline of more than one
+// command, or a command carrying redirects) is worth wrapping in `Stmt::List` when scanning a
+// script; a single bare pipeline keeps the plain `Stmt::Command` shape so that a `;` or newline
+// after it is just read as the ordinary statement separator it already doubles as, not a
+// sequencing terminator. Redirects count too since `command()` has no notion of them at all.
+fn is_compound(a: &ast::AndOr) -> bool {
+    !a.tail.is_empty() || a.head.len() > 1 || a.head.iter().any(|cmd| !cmd.redirects.is_empty())
+}
+
+// `list_of_commands()` always has an explicit terminator in hand, so it never needs to fall back
+// to `command()`: a backgrounded (`&`) list is promoted to `Stmt::List` even for a single bare
+// command, since backgrounding needs to be recorded; otherwise only a genuinely compound list
+// (see `is_compound`) is worth wrapping, and a single bare command collapses to the same
+// `Stmt::Command` shape `command()` would have produced for the same text. This is the one place
+// that decision is made, so `stmt()` calls it directly instead of re-deriving it, and a caller of
+// `list_of_commands()` never sees a different shape than `script()` gives the same input.
+fn stmt_from_terminated_list(a: ast::AndOr, t: ast::Terminator) -> ast::Stmt {
+    if matches!(t, ast::Terminator::Async) || is_compound(&a) {
+        return ast::Stmt::List(a, t);
+    }
+    let mut pipeline = a.head;
+    let cmd = pipeline
+        .pop()
+        .expect("a non-compound and/or list has exactly one command");
+    ast::Stmt::Command(cmd.name, cmd.args)
+}
+
+// A bare and/or list with no terminator is only promoted to `Stmt::List` when it's genuinely
+// compound; a single bare command is left for `command()` to parse instead, since there's no
+// terminator here for `list_of_commands()` to consume.
+fn promote_unterminated_and_or(a: ast::AndOr) -> Option<ast::Stmt> {
+    if is_compound(&a) {
+        Some(ast::Stmt::List(a, ast::Terminator::Sequential))
+    } else {
+        None
     }
 }
 
@@ -27,15 +207,54 @@ peg::parser! {
         // ## Words
         //
         // The following characters have special meanings:
-        rule chr() = !['#' | '$' | '|' | '&' | ';' | '(' | ')' | '<' | '>' | ' ' | '\t' | '\n'] [_]
+        rule chr() = !['#' | '$' | '|' | '&' | ';' | '(' | ')' | '<' | '>' | '^' | ' ' | '\t' | '\n'] [_]
         //
-        // Special characters terminate words.
-        pub rule word_unquoted() -> ast::Arg = w:$(chr()+) { ast::Arg::Word(w.to_string()) }
+        // A word is a run of one or more segments: literal text, or a `$name` interpolation.
+        // Scanning alternates between the two until a terminator is reached, so a word can mix
+        // literal and variable content, e.g. `$dir/file.$ext`.
+        rule segment() -> ast::Segment
+            = "$" n:name() { ast::Segment { text: n, kind: ast::SegmentKind::Variable } }
+            / t:$(chr()+) { ast::Segment { text: t.to_string(), kind: ast::SegmentKind::Literal } }
         //
-        // The single quote prevents special treatment of any character other than itself.
-        pub rule word_quoted() -> ast::Arg = "'" s:$((!"'" [_])*) "'" { ast::Arg::Word(s.to_string()) }
+        // Special characters terminate words. A word made of a single variable segment is the
+        // same thing `reference()` parses, so it collapses to `Arg::Var` rather than a one-segment
+        // `Arg::Word`.
+        pub rule word_unquoted() -> ast::Arg
+            = segs:segment()+ {
+                match &segs[..] {
+                    [ast::Segment { kind: ast::SegmentKind::Variable, text }] => ast::Arg::Var(text.clone()),
+                    _ => ast::Arg::Word(segs),
+                }
+            }
         //
-        pub rule word() -> ast::Arg = word_quoted() / word_unquoted()
+        // The single quote prevents special treatment of any character other than itself, so a
+        // quoted word is always a single literal segment, even if it looks like `$name`.
+        pub rule word_quoted() -> ast::Arg
+            = "'" s:$((!"'" [_])*) "'" {
+                ast::Arg::Word(vec![ast::Segment { text: s.to_string(), kind: ast::SegmentKind::Literal }])
+            }
+        //
+        // The double quote still allows `$name` interpolation and understands backslash
+        // escapes, unlike the single quote. A literal run stops at an unescaped `"` or `$`. A
+        // `$` not followed by a name character (e.g. trailing at end of word, or before a
+        // space) has nothing to interpolate, so it falls back to a literal `$` rather than
+        // failing the whole word; escape it with `\$` to get a literal `$` unambiguously.
+        rule dq_segment() -> ast::Segment
+            = "$" n:name() { ast::Segment { text: n, kind: ast::SegmentKind::Variable } }
+            / t:$((!['"' | '$' | '\\'] [_] / "\\" [_])+) {
+                ast::Segment { text: strip_basic_escape(t), kind: ast::SegmentKind::Literal }
+            }
+            / "$" { ast::Segment { text: "$".to_string(), kind: ast::SegmentKind::Literal } }
+        pub rule word_double_quoted() -> ast::Arg
+            = "\"" segs:dq_segment()* "\"" {
+                if segs.is_empty() {
+                    ast::Arg::Word(vec![ast::Segment { text: String::new(), kind: ast::SegmentKind::Literal }])
+                } else {
+                    ast::Arg::Word(segs)
+                }
+            }
+        //
+        pub rule word() -> ast::Arg = word_double_quoted() / word_quoted() / word_unquoted()
 
 
         // ## Variables
@@ -55,10 +274,46 @@ peg::parser! {
         //
         // The primary data structure is the list, which is a sequence of words. Parentheses are
         // used to group lists. The empty list is represented by ().
-        pub rule arg() -> ast::Arg = reference() / word()
+        //
+        // `word()` is tried before `reference()`: a bare `$name` still collapses to `Arg::Var`
+        // (see `word_unquoted()`), but only `word()`'s segment scanning can consume a `$name`
+        // that has further literal text glued onto it, such as `$dir/file`.
+        pub rule arg() -> ast::Arg = word() / reference()
+        //
+        // An operand to `^` is either a single arg or a parenthesised list.
+        rule operand() -> ast::List
+            = "(" x:(element() ** _) ")" { x.into_iter().flatten().collect() }
+            / a:arg() { vec![a] }
+        //
+        // `^` joins operands left-to-right, distributing across multi-element lists per rc's
+        // "free careting" rule (see `concat()`).
+        pub rule element() -> ast::List
+            = first:operand() rest:("^" o:operand() { o })+ {?
+                rest.into_iter().try_fold(first, concat)
+            }
+            / operand()
         pub rule list() -> ast::List
-            = "(" x:(arg() ** _) ")" { x }
-            / x:(arg() ** _) { x }
+            = "(" x:(element() ** _) ")" { x.into_iter().flatten().collect() }
+            / x:(element() ** _) { x.into_iter().flatten().collect() }
+
+
+        // ## Redirections
+        //
+        // A redirection attaches a file, or a duplicate of another descriptor, to one of a
+        // command's file descriptors. The descriptor number may be given explicitly before the
+        // operator; otherwise `<` defaults to 0 (stdin) and `>`/`>>` default to 1 (stdout).
+        rule fd() -> i32 = n:$(['0'..='9']+) {? n.parse().or(Err("fd number out of range")) }
+        //
+        // The file target is scanned with `element()` rather than `arg()` so that `^`
+        // concatenation, e.g. `>$prefix^.txt`, is reachable here too; `redirect_target()` rejects
+        // the rare case where that concatenation still yields more than one value. Whitespace is
+        // allowed, but not required, both after a leading fd number and after the operator itself,
+        // since `2>err`, `2 >err`, `2> err` and `>out` are all legal.
+        pub rule redirect() -> ast::Redirect
+            = from:fd()? _* ">>" _* f:element() {? redirect_target(f).map(|f| ast::Redirect { from: from.unwrap_or(1), to: ast::RedirectTo::File(f), dir: ast::RedirectDir::Append }) }
+            / from:fd()? _* ">&" _* n:fd() { ast::Redirect { from: from.unwrap_or(1), to: ast::RedirectTo::Fd(n), dir: ast::RedirectDir::Out } }
+            / from:fd()? _* ">" _* f:element() {? redirect_target(f).map(|f| ast::Redirect { from: from.unwrap_or(1), to: ast::RedirectTo::File(f), dir: ast::RedirectDir::Out }) }
+            / from:fd()? _* "<" _* f:element() {? redirect_target(f).map(|f| ast::Redirect { from: from.unwrap_or(0), to: ast::RedirectTo::File(f), dir: ast::RedirectDir::In }) }
 
 
         // ## Statements
@@ -66,32 +321,116 @@ peg::parser! {
         pub rule assignment() -> ast::Stmt
             = n:arg() _ "=" _ x:list() { ast::Stmt::Assignment(n, x) }
 
+        // The separating whitespace is optional before the list so that a command with no
+        // arguments, such as a bare `%a` on its own line, still parses.
         pub rule command() -> ast::Stmt
-            = n:arg() _ x:list() { ast::Stmt::Command(n, x) }
+            = n:arg() _* x:list() { ast::Stmt::Command(n, x) }
+
+        // A pipeline command is a command name followed by any mixture of arguments and
+        // redirections, e.g. `sort <in >out` or `grep -v foo 2>err`. Arguments are scanned with
+        // `element()` rather than `arg()` so that `^` concatenation, e.g. `$prefix^.txt`, is
+        // reachable here too.
+        rule pipeline_part() -> CommandPart
+            = r:redirect() { CommandPart::Redirect(r) }
+            / a:element() { CommandPart::Args(a) }
+        rule pipeline_command() -> ast::Command
+            = n:arg() parts:(_* p:pipeline_part() { p })* {
+                let mut args = ast::List::new();
+                let mut redirects = Vec::new();
+                for part in parts {
+                    match part {
+                        CommandPart::Args(a) => args.extend(a),
+                        CommandPart::Redirect(r) => redirects.push(r),
+                    }
+                }
+                ast::Command { name: n, args, redirects }
+            }
+        //
+        // A pipeline chains one or more commands together with `|`, feeding each command's
+        // output to the next one's input. There's no standalone `Stmt`-producing rule for a bare
+        // pipeline: `and_or()` below already covers the single-pipeline case (as an `AndOr` with
+        // an empty `tail`), and that's the only shape `stmt()`/`script()` produce for one.
+        rule pipeline_list() -> ast::Pipeline = pipeline_command() ++ (_* "|" _*)
+
+
+        // ## Command lists
+        //
+        // `&&` and `||` chain pipelines together, short-circuiting on the previous pipeline's
+        // success or failure respectively; they bind tighter than the `;`/`&` that terminate
+        // the whole list.
+        rule connector() -> ast::Connector
+            = "&&" { ast::Connector::And }
+            / "||" { ast::Connector::Or }
+        pub rule and_or() -> ast::AndOr
+            = head:pipeline_list() tail:(_* c:connector() _* p:pipeline_list() { (c, p) })* {
+                ast::AndOr { head, tail }
+            }
+        //
+        // `;` runs the list to completion before the next one starts; `&` backgrounds it and
+        // moves on immediately. A newline does the same job as `;` when nothing else ends the
+        // list first.
+        rule terminator() -> ast::Terminator
+            = ";" { ast::Terminator::Sequential }
+            / "&" { ast::Terminator::Async }
+            / "\n" { ast::Terminator::Sequential }
+        pub rule list_of_commands() -> ast::Stmt
+            = a:and_or() _* t:terminator() { stmt_from_terminated_list(a, t) }
+
+
+        // ## Scripts
+        //
+        // A `#` starts a comment that runs to the end of the line. `#` is already a word
+        // terminator, but nothing previously consumed it.
+        rule comment() = quiet!{ "#" (!['\n'] [_])* }
+        //
+        // Whitespace (including newlines), comments and `;` may all appear, in any mixture,
+        // between statements; a run of them separates one statement from the next.
+        rule ws() = quiet!{ [' ' | '\t' | '\n'] }
+        rule sep() = quiet!{ (ws() / comment() / ";")* }
+        //
+        // A pipeline or and/or list is only promoted out of plain `command()` when it's
+        // genuinely compound, or explicitly backgrounded with `&` (see
+        // `stmt_from_terminated_list()`/`promote_unterminated_and_or()`); this keeps a bare
+        // `%a;` or `%a\n` reading as a plain `Stmt::Command`, as it always has. The terminated
+        // case defers to `list_of_commands()` directly rather than re-deriving the same
+        // decision, so the two rules can never disagree about the shape of identical input.
+        rule stmt() -> ast::Stmt
+            = assignment()
+            / list_of_commands()
+            / a:and_or() {? promote_unterminated_and_or(a).ok_or("not a compound statement") }
+            / command()
+        //
+        // A script is a sequence of statements, one per line (or `;`-separated), with blank
+        // lines and comments skipped wherever a statement could start. A statement that ends in
+        // an explicit terminator (`;`, `&`, or a newline) has already consumed its own
+        // separator, so the gap before the next statement may be empty.
+        pub rule script() -> Vec<ast::Stmt>
+            = sep() stmts:(stmt() ** sep()) sep() { stmts }
 
     }
 }
 
 #[cfg(test)]
 mod tests {
-    // use std::fs;
+    use std::fs;
     use super::*;
 
+    fn word(s: &str) -> ast::Arg {
+        ast::Arg::Word(vec![ast::Segment {
+            text: s.to_string(),
+            kind: ast::SegmentKind::Literal,
+        }])
+    }
+
     // from https://stackoverflow.com/questions/38183551
     macro_rules! word_vec {
-        ($($x:expr),*) => (vec![$(ast::Arg::Word($x.to_string())),*]);
+        ($($x:expr),*) => (vec![$(word($x)),*]);
     }
 
     #[test]
     fn string() {
-        assert_eq!(
-            parser::word("''"),
-            Ok(ast::Arg::Word(String::from("")))
-        );
-        assert_eq!(
-            parser::word_quoted("'Hello world'"),
-            Ok(ast::Arg::Word(String::from("Hello world")))
-        );
+        assert_eq!(parser::word("''"), Ok(word("")));
+        assert_eq!(parser::word_quoted("'Hello world'"), Ok(word("Hello world")));
     }
 
     #[test]
@@ -139,10 +478,26 @@ mod tests {
     fn list_with_variable_references() {
         assert_eq!(
             parser::list("Hello $name"),
-            Ok(vec![
-                ast::Arg::Word("Hello".to_string()),
-                ast::Arg::Var("name".to_string())
-            ])
+            Ok(vec![word("Hello"), ast::Arg::Var("name".to_string())])
+        );
+    }
+
+    #[test]
+    fn word_with_interpolated_segments() {
+        assert_eq!(
+            parser::word("$dir/file.$ext"),
+            Ok(ast::Arg::Word(vec![
+                ast::Segment { text: "dir".to_string(), kind: ast::SegmentKind::Variable },
+                ast::Segment { text: "/file.".to_string(), kind: ast::SegmentKind::Literal },
+                ast::Segment { text: "ext".to_string(), kind: ast::SegmentKind::Variable },
+            ]))
+        );
+        assert_eq!(
+            parser::word("$prefix.txt"),
+            Ok(ast::Arg::Word(vec![
+                ast::Segment { text: "prefix".to_string(), kind: ast::SegmentKind::Variable },
+                ast::Segment { text: ".txt".to_string(), kind: ast::SegmentKind::Literal },
+            ]))
         );
     }
 
@@ -150,33 +505,27 @@ mod tests {
     fn assignment() {
         assert_eq!(
             parser::assignment("a = 1"),
-            Ok(ast::Stmt::Assignment(ast::Arg::Word(String::from("a")), word_vec!["1"]))
+            Ok(ast::Stmt::Assignment(word("a"), word_vec!["1"]))
         );
         assert_eq!(
             parser::assignment("list = (a b c)"),
-            Ok(ast::Stmt::Assignment(
-                ast::Arg::Word(String::from("list")),
-                word_vec!["a", "b", "c"]
-            ))
+            Ok(ast::Stmt::Assignment(word("list"), word_vec!["a", "b", "c"]))
         );
         assert_eq!(
             parser::assignment("s = ('Hello world')"),
-            Ok(ast::Stmt::Assignment(
-                ast::Arg::Word(String::from("s")),
-                word_vec!["Hello world"]
-            ))
+            Ok(ast::Stmt::Assignment(word("s"), word_vec!["Hello world"]))
         );
         assert_eq!(
             parser::assignment("hello = Hello 'Laurence de Bruxelles'"),
             Ok(ast::Stmt::Assignment(
-                ast::Arg::Word(String::from("hello")),
+                word("hello"),
                 word_vec!["Hello", "Laurence de Bruxelles"]
             ))
         );
         assert_eq!(
             parser::assignment("this = $that"),
             Ok(ast::Stmt::Assignment(
-                ast::Arg::Word("this".to_string()),
+                word("this"),
                 vec![ast::Arg::Var("that".to_string())]
             ))
         );
@@ -187,8 +536,8 @@ mod tests {
         assert_eq!(
             parser::assignment("$pointer = value"),
             Ok(ast::Stmt::Assignment(
-                    ast::Arg::Var("pointer".to_string()),
-                    word_vec!["value"]
+                ast::Arg::Var("pointer".to_string()),
+                word_vec!["value"]
             ))
         );
     }
@@ -198,11 +547,8 @@ mod tests {
         assert_eq!(
             parser::command("%echo Hello $name"),
             Ok(ast::Stmt::Command(
-                ast::Arg::Word("%echo".to_string()),
-                vec![
-                    ast::Arg::Word("Hello".to_string()),
-                    ast::Arg::Var("name".to_string())
-                ]
+                word("%echo"),
+                vec![word("Hello"), ast::Arg::Var("name".to_string())]
             ))
         );
     }
@@ -212,17 +558,265 @@ mod tests {
         assert_eq!(
             parser::command("$command 1 2"),
             Ok(ast::Stmt::Command(
-                    ast::Arg::Var("command".to_string()),
-                    word_vec!["1", "2"]
+                ast::Arg::Var("command".to_string()),
+                word_vec!["1", "2"]
             ))
         );
     }
 
-    /*
     #[test]
-    fn comments() {
-        assert_eq!(parser::lines("# Hello World"), Ok(vec![1]));
-        assert_eq!(parser::lines("# Hello World\n# 2nd line"), Ok(vec![1, 1]));
+    fn redirect() {
+        assert_eq!(
+            parser::redirect("<in"),
+            Ok(ast::Redirect {
+                from: 0,
+                to: ast::RedirectTo::File(word("in")),
+                dir: ast::RedirectDir::In,
+            })
+        );
+        assert_eq!(
+            parser::redirect(">out"),
+            Ok(ast::Redirect {
+                from: 1,
+                to: ast::RedirectTo::File(word("out")),
+                dir: ast::RedirectDir::Out,
+            })
+        );
+        assert_eq!(
+            parser::redirect(">>out"),
+            Ok(ast::Redirect {
+                from: 1,
+                to: ast::RedirectTo::File(word("out")),
+                dir: ast::RedirectDir::Append,
+            })
+        );
+        assert_eq!(
+            parser::redirect("2>err"),
+            Ok(ast::Redirect {
+                from: 2,
+                to: ast::RedirectTo::File(word("err")),
+                dir: ast::RedirectDir::Out,
+            })
+        );
+        assert_eq!(
+            parser::redirect(">&2"),
+            Ok(ast::Redirect {
+                from: 1,
+                to: ast::RedirectTo::Fd(2),
+                dir: ast::RedirectDir::Out,
+            })
+        );
+    }
+
+    #[test]
+    fn redirect_allows_whitespace_around_the_operator() {
+        assert_eq!(
+            parser::redirect("> out"),
+            Ok(ast::Redirect {
+                from: 1,
+                to: ast::RedirectTo::File(word("out")),
+                dir: ast::RedirectDir::Out,
+            })
+        );
+        assert_eq!(
+            parser::redirect("< in"),
+            Ok(ast::Redirect {
+                from: 0,
+                to: ast::RedirectTo::File(word("in")),
+                dir: ast::RedirectDir::In,
+            })
+        );
+        assert_eq!(
+            parser::redirect("2 > err"),
+            Ok(ast::Redirect {
+                from: 2,
+                to: ast::RedirectTo::File(word("err")),
+                dir: ast::RedirectDir::Out,
+            })
+        );
+    }
+
+    #[test]
+    fn redirect_target_accepts_caret_concatenation() {
+        assert_eq!(
+            parser::redirect(">$prefix^.txt"),
+            Ok(ast::Redirect {
+                from: 1,
+                to: ast::RedirectTo::File(ast::Arg::Concat(
+                    Box::new(ast::Arg::Var("prefix".to_string())),
+                    Box::new(word(".txt"))
+                )),
+                dir: ast::RedirectDir::Out,
+            })
+        );
+    }
+
+    #[test]
+    fn redirect_target_rejects_multi_value_concatenation() {
+        assert!(parser::redirect(">(a b)^(1 2)").is_err());
+    }
+
+    #[test]
+    fn pipeline_with_redirects() {
+        assert_eq!(
+            parser::and_or("%sort <in >out"),
+            Ok(ast::AndOr {
+                head: vec![ast::Command {
+                    name: word("%sort"),
+                    args: vec![],
+                    redirects: vec![
+                        ast::Redirect {
+                            from: 0,
+                            to: ast::RedirectTo::File(word("in")),
+                            dir: ast::RedirectDir::In,
+                        },
+                        ast::Redirect {
+                            from: 1,
+                            to: ast::RedirectTo::File(word("out")),
+                            dir: ast::RedirectDir::Out,
+                        },
+                    ],
+                }],
+                tail: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn pipeline_with_redirects_allows_doubled_spaces() {
+        assert_eq!(
+            parser::and_or("%sort  <in  >out"),
+            Ok(ast::AndOr {
+                head: vec![ast::Command {
+                    name: word("%sort"),
+                    args: vec![],
+                    redirects: vec![
+                        ast::Redirect {
+                            from: 0,
+                            to: ast::RedirectTo::File(word("in")),
+                            dir: ast::RedirectDir::In,
+                        },
+                        ast::Redirect {
+                            from: 1,
+                            to: ast::RedirectTo::File(word("out")),
+                            dir: ast::RedirectDir::Out,
+                        },
+                    ],
+                }],
+                tail: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn pipeline_multiple_commands() {
+        assert_eq!(
+            parser::and_or("%a | %b | %c"),
+            Ok(ast::AndOr {
+                head: vec![
+                    ast::Command { name: word("%a"), args: vec![], redirects: vec![] },
+                    ast::Command { name: word("%b"), args: vec![], redirects: vec![] },
+                    ast::Command { name: word("%c"), args: vec![], redirects: vec![] },
+                ],
+                tail: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn pipeline_allows_no_whitespace_around_the_pipe() {
+        assert_eq!(
+            parser::and_or("%a|%b|%c"),
+            Ok(ast::AndOr {
+                head: vec![
+                    ast::Command { name: word("%a"), args: vec![], redirects: vec![] },
+                    ast::Command { name: word("%b"), args: vec![], redirects: vec![] },
+                    ast::Command { name: word("%c"), args: vec![], redirects: vec![] },
+                ],
+                tail: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn script_statement_separators() {
+        assert_eq!(
+            parser::script("%a\n%b"),
+            Ok(vec![
+                ast::Stmt::Command(word("%a"), vec![]),
+                ast::Stmt::Command(word("%b"), vec![]),
+            ])
+        );
+        assert_eq!(
+            parser::script("%a; %b"),
+            Ok(vec![
+                ast::Stmt::Command(word("%a"), vec![]),
+                ast::Stmt::Command(word("%b"), vec![]),
+            ])
+        );
+        assert_eq!(
+            parser::script("%a\n\n%b"),
+            Ok(vec![
+                ast::Stmt::Command(word("%a"), vec![]),
+                ast::Stmt::Command(word("%b"), vec![]),
+            ])
+        );
+    }
+
+    #[test]
+    fn script_bare_redirect_only_command() {
+        assert_eq!(
+            parser::script("%sort <in >out"),
+            Ok(vec![ast::Stmt::List(
+                ast::AndOr {
+                    head: vec![ast::Command {
+                        name: word("%sort"),
+                        args: vec![],
+                        redirects: vec![
+                            ast::Redirect {
+                                from: 0,
+                                to: ast::RedirectTo::File(word("in")),
+                                dir: ast::RedirectDir::In,
+                            },
+                            ast::Redirect {
+                                from: 1,
+                                to: ast::RedirectTo::File(word("out")),
+                                dir: ast::RedirectDir::Out,
+                            },
+                        ],
+                    }],
+                    tail: vec![],
+                },
+                ast::Terminator::Sequential
+            )])
+        );
+    }
+
+    #[test]
+    fn script_comments() {
+        assert_eq!(parser::script("# Hello World"), Ok(vec![]));
+        assert_eq!(parser::script("# Hello World\n# 2nd line"), Ok(vec![]));
+        assert_eq!(
+            parser::script("# a leading comment\n%a # a trailing comment\n%b"),
+            Ok(vec![
+                ast::Stmt::Command(word("%a"), vec![]),
+                ast::Stmt::Command(word("%b"), vec![]),
+            ])
+        );
+    }
+
+    #[test]
+    fn script_mixes_assignments_and_commands() {
+        assert_eq!(
+            parser::script("name = world\n%echo Hello $name"),
+            Ok(vec![
+                ast::Stmt::Assignment(word("name"), word_vec!["world"]),
+                ast::Stmt::Command(
+                    word("%echo"),
+                    vec![word("Hello"), ast::Arg::Var("name".to_string())]
+                ),
+            ])
+        );
     }
 
     #[test]
@@ -230,7 +824,180 @@ mod tests {
         let script = fs::read_to_string("examples/hello.rcsh")
             .expect("could not read test file");
 
-        println!("{}", script)
+        assert_eq!(
+            parser::script(&script),
+            Ok(vec![
+                ast::Stmt::Assignment(word("name"), word_vec!["world"]),
+                ast::Stmt::Command(
+                    word("%echo"),
+                    vec![word("Hello"), ast::Arg::Var("name".to_string())]
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn caret_concatenation() {
+        assert_eq!(
+            parser::element("$prefix^.txt"),
+            Ok(vec![ast::Arg::Concat(
+                Box::new(ast::Arg::Var("prefix".to_string())),
+                Box::new(word(".txt"))
+            )])
+        );
+    }
+
+    #[test]
+    fn caret_concatenation_distributes_over_a_list() {
+        assert_eq!(
+            parser::element("pre^(x y)"),
+            Ok(vec![
+                ast::Arg::Concat(Box::new(word("pre")), Box::new(word("x"))),
+                ast::Arg::Concat(Box::new(word("pre")), Box::new(word("y"))),
+            ])
+        );
+    }
+
+    #[test]
+    fn caret_concatenation_distributes_pairwise_across_two_lists() {
+        assert_eq!(
+            parser::element("(a b c)^(1 2 3)"),
+            Ok(vec![
+                ast::Arg::Concat(Box::new(word("a")), Box::new(word("1"))),
+                ast::Arg::Concat(Box::new(word("b")), Box::new(word("2"))),
+                ast::Arg::Concat(Box::new(word("c")), Box::new(word("3"))),
+            ])
+        );
+    }
+
+    #[test]
+    fn caret_concatenation_rejects_mismatched_list_lengths() {
+        assert!(parser::element("(a b c)^(1 2)").is_err());
+    }
+
+    #[test]
+    fn word_double_quoted_escapes() {
+        assert_eq!(
+            parser::word_double_quoted(r#""a\tb\nc""#),
+            Ok(word("a\tb\nc"))
+        );
+        assert_eq!(
+            parser::word_double_quoted(r#""say \"hi\" to \$5""#),
+            Ok(word("say \"hi\" to $5"))
+        );
+        assert_eq!(
+            parser::word_double_quoted(r#""a\xb""#),
+            Ok(word("axb"))
+        );
+    }
+
+    #[test]
+    fn word_double_quoted_empty() {
+        assert_eq!(parser::word_double_quoted(r#""""#), Ok(word("")));
+    }
+
+    #[test]
+    fn word_double_quoted_interpolates_variables() {
+        assert_eq!(
+            parser::word_double_quoted(r#""Hello $name!""#),
+            Ok(ast::Arg::Word(vec![
+                ast::Segment { text: "Hello ".to_string(), kind: ast::SegmentKind::Literal },
+                ast::Segment { text: "name".to_string(), kind: ast::SegmentKind::Variable },
+                ast::Segment { text: "!".to_string(), kind: ast::SegmentKind::Literal },
+            ]))
+        );
+    }
+
+    #[test]
+    fn word_double_quoted_bare_dollar_is_literal() {
+        assert_eq!(
+            parser::word_double_quoted(r#""price $""#),
+            Ok(ast::Arg::Word(vec![
+                ast::Segment { text: "price ".to_string(), kind: ast::SegmentKind::Literal },
+                ast::Segment { text: "$".to_string(), kind: ast::SegmentKind::Literal },
+            ]))
+        );
+        assert_eq!(
+            parser::word_double_quoted(r#""a $ b""#),
+            Ok(ast::Arg::Word(vec![
+                ast::Segment { text: "a ".to_string(), kind: ast::SegmentKind::Literal },
+                ast::Segment { text: "$".to_string(), kind: ast::SegmentKind::Literal },
+                ast::Segment { text: " b".to_string(), kind: ast::SegmentKind::Literal },
+            ]))
+        );
+    }
+
+    fn bare_command(name: &str) -> ast::Command {
+        ast::Command { name: word(name), args: vec![], redirects: vec![] }
+    }
+
+    #[test]
+    fn and_or_single_pipeline() {
+        assert_eq!(
+            parser::and_or("%a"),
+            Ok(ast::AndOr { head: vec![bare_command("%a")], tail: vec![] })
+        );
+    }
+
+    #[test]
+    fn and_or_chains_connectors() {
+        assert_eq!(
+            parser::and_or("%a && %b || %c"),
+            Ok(ast::AndOr {
+                head: vec![bare_command("%a")],
+                tail: vec![
+                    (ast::Connector::And, vec![bare_command("%b")]),
+                    (ast::Connector::Or, vec![bare_command("%c")]),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn and_or_binds_tighter_than_terminator() {
+        assert_eq!(
+            parser::list_of_commands("%a && %b;"),
+            Ok(ast::Stmt::List(
+                ast::AndOr {
+                    head: vec![bare_command("%a")],
+                    tail: vec![(ast::Connector::And, vec![bare_command("%b")])],
+                },
+                ast::Terminator::Sequential
+            ))
+        );
+    }
+
+    #[test]
+    fn list_of_commands_async_terminator() {
+        assert_eq!(
+            parser::list_of_commands("%a &"),
+            Ok(ast::Stmt::List(
+                ast::AndOr { head: vec![bare_command("%a")], tail: vec![] },
+                ast::Terminator::Async
+            ))
+        );
+    }
+
+    #[test]
+    fn list_of_commands_agrees_with_script_for_a_non_compound_line() {
+        // A single bare command followed by a terminator is not "compound", so
+        // `list_of_commands()` must collapse it to the same `Stmt::Command` shape `script()`
+        // produces for identical text, rather than wrapping it in `Stmt::List`.
+        assert_eq!(
+            parser::list_of_commands("%a;"),
+            Ok(ast::Stmt::Command(word("%a"), vec![]))
+        );
+        assert_eq!(
+            parser::list_of_commands("%a;"),
+            Ok(parser::script("%a;").unwrap().into_iter().next().unwrap())
+        );
+    }
+
+    /*
+    #[test]
+    fn comments() {
+        assert_eq!(parser::lines("# Hello World"), Ok(vec![1]));
+        assert_eq!(parser::lines("# Hello World\n# 2nd line"), Ok(vec![1, 1]));
     }
     */
 }